@@ -1,7 +1,13 @@
-use ff::Field;
+use ff::{Field, PrimeField};
+use std::ops::{Div, Mul};
+use subtle::Choice;
+
+mod quorum;
+pub use quorum::Quorum;
 
 /// A Multiset hash. This is a just a finite field element.
 /// We use a slightly different notion of "multiset" than elsewhere. The biggest difference is we allow negative multiplicities. See the readme for more information.
+#[derive(Debug, Clone, Copy)]
 pub struct MultisetHash<F>(pub(crate) F);
 
 impl<F: Field> MultisetHash<F> {
@@ -58,8 +64,184 @@ impl<F: Field> MultisetHash<F> {
 
         MultisetHash(self.0 * inv.unwrap())
     }
+
+    /// returns `true` if the underlying multiset is empty, i.e. every element has multiplicity zero.
+    pub fn is_empty(&self) -> bool {
+        self.0 == F::one()
+    }
+
+    /// returns the underlying field element backing this hash.
+    pub fn hash(&self) -> F {
+        self.0
+    }
+
+    /// compares two multiset hashes in constant time. [`Eq`]/[`PartialEq`] are built on this rather than comparing [`MultisetHash::hash`] directly.
+    pub fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+
+    /// builds a [`MultisetHash`] from an iterator of `(elem, count)` pairs where `elem` implements [`HashToField`], hashing each element to the field before adding it. Just a convenience wrapper around [`MultisetHash::extend_elems`].
+    pub fn from_elems<T: HashToField<F>>(iter: impl IntoIterator<Item = (T, u64)>) -> Self {
+        let mut hash = MultisetHash::new();
+        hash.extend_elems(iter);
+        hash
+    }
+
+    /// folds an iterator of `(elem, count)` pairs where `elem` implements [`HashToField`] into the multiset, hashing each element to the field before adding it. Just a convenience wrapper around [`MultisetHash::add_elem`].
+    pub fn extend_elems<T: HashToField<F>>(&mut self, iter: impl IntoIterator<Item = (T, u64)>) {
+        for (elem, count) in iter {
+            *self = self.add_elem(elem, count);
+        }
+    }
+
+    /// builds a [`MultisetHash`] for the multiset described by `entries` via windowed multi-exponentiation, rather than one [`Field::pow_vartime`] call per entry. Matches folding [`MultisetHash::add`] over `entries`, except a zero element with a nonzero count panics here instead of silently collapsing the hash to zero.
+    pub fn from_slice(entries: &[(F, u64)]) -> Self {
+        MultisetHash(windowed_product(entries))
+    }
+
+    /// same as [`MultisetHash::from_slice`], but works on any type that implements [`HashToField`] and hashes it to the field before accumulating it.
+    pub fn from_slice_elems<T: HashToField<F>>(entries: &[(T, u64)]) -> Self {
+        let hashed: Vec<(F, u64)> = entries
+            .iter()
+            .map(|(elem, count)| (HashToField::hash_to_field(elem), *count))
+            .collect();
+        MultisetHash(windowed_product(&hashed))
+    }
 }
 
+impl<F: PrimeField> MultisetHash<F> {
+    /// returns the field's canonical little-endian representation of this hash.
+    pub fn to_repr(&self) -> F::Repr {
+        self.0.to_repr()
+    }
+
+    /// serializes this hash to its field's canonical little-endian byte encoding, suitable for persisting a commitment or shipping it to a peer for an equality check.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_repr().as_ref().to_vec()
+    }
+
+    /// deserializes a hash from its canonical little-endian byte encoding, as produced by [`MultisetHash::to_bytes`]. Returns `None` if `bytes` is not a canonical encoding of a field element, or if it encodes zero, since `0` can never be a valid multiset hash.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut repr = F::Repr::default();
+        if repr.as_ref().len() != bytes.len() {
+            return None;
+        }
+        repr.as_mut().copy_from_slice(bytes);
+
+        let field = F::from_repr(repr);
+        if bool::from(field.is_none()) {
+            return None;
+        }
+        let field = field.unwrap();
+        if bool::from(field.is_zero()) {
+            return None;
+        }
+
+        Some(MultisetHash(field))
+    }
+}
+
+/// window width, in bits, used by the Pippenger-style bucket method backing [`MultisetHash::from_slice`].
+const WINDOW_BITS: u32 = 4;
+
+/// computes `∏ elem_i^{count_i}` via Pippenger's windowed bucket method: each `WINDOW_BITS`-wide
+/// digit of the counts buckets its element, buckets collapse into a window product via a
+/// running-prefix-product, and window products combine from the most significant window down.
+fn windowed_product<F: Field>(entries: &[(F, u64)]) -> F {
+    for &(elem, count) in entries {
+        if count > 0 && bool::from(elem.is_zero()) {
+            panic!("elements must be nonzero");
+        }
+    }
+
+    let num_buckets = (1u64 << WINDOW_BITS) - 1;
+    let num_windows = u64::BITS.div_ceil(WINDOW_BITS);
+
+    let mut acc = F::one();
+    for w in (0..num_windows).rev() {
+        let mut buckets = vec![F::one(); num_buckets as usize];
+        for &(elem, count) in entries {
+            let digit = (count >> (w * WINDOW_BITS)) & num_buckets;
+            if digit == 0 {
+                continue;
+            }
+            buckets[digit as usize - 1] *= elem;
+        }
+
+        let mut running = F::one();
+        let mut window_product = F::one();
+        for bucket in buckets.into_iter().rev() {
+            running *= bucket;
+            window_product *= running;
+        }
+
+        for _ in 0..WINDOW_BITS {
+            acc = acc.square();
+        }
+        acc *= window_product;
+    }
+
+    acc
+}
+
+impl<F: Field> FromIterator<(F, u64)> for MultisetHash<F> {
+    /// builds a [`MultisetHash`] from an iterator of `(elem, count)` pairs.
+    fn from_iter<I: IntoIterator<Item = (F, u64)>>(iter: I) -> Self {
+        let mut hash = MultisetHash::new();
+        hash.extend(iter);
+        hash
+    }
+}
+
+impl<F: Field> FromIterator<F> for MultisetHash<F> {
+    /// builds a [`MultisetHash`] from an iterator of elements, each with multiplicity 1.
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        iter.into_iter().map(|elem| (elem, 1)).collect()
+    }
+}
+
+impl<F: Field> Extend<(F, u64)> for MultisetHash<F> {
+    /// folds an iterator of `(elem, count)` pairs into the multiset.
+    fn extend<I: IntoIterator<Item = (F, u64)>>(&mut self, iter: I) {
+        for (elem, count) in iter {
+            *self = self.add(elem, count);
+        }
+    }
+}
+
+impl<F: Field> Extend<F> for MultisetHash<F> {
+    /// folds an iterator of elements into the multiset, each with multiplicity 1.
+    fn extend<I: IntoIterator<Item = F>>(&mut self, iter: I) {
+        Extend::extend(self, iter.into_iter().map(|elem| (elem, 1)));
+    }
+}
+
+impl<F: Field> Mul<&MultisetHash<F>> for &MultisetHash<F> {
+    type Output = MultisetHash<F>;
+
+    /// equivalent to [`MultisetHash::multiset_union`].
+    fn mul(self, other: &MultisetHash<F>) -> MultisetHash<F> {
+        self.multiset_union(other)
+    }
+}
+
+impl<F: Field> Div<&MultisetHash<F>> for &MultisetHash<F> {
+    type Output = MultisetHash<F>;
+
+    /// equivalent to [`MultisetHash::multiset_difference`].
+    fn div(self, other: &MultisetHash<F>) -> MultisetHash<F> {
+        self.multiset_difference(other)
+    }
+}
+
+impl<F: Field> PartialEq for MultisetHash<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<F: Field> Eq for MultisetHash<F> {}
+
 pub trait HashToField<F: Field> {
     fn hash_to_field(&self) -> F;
 }
@@ -72,23 +254,23 @@ mod tests {
     #[test]
     fn test_single_ops() {
         let mut mh = MultisetHash::<Scalar>::new();
-        assert_eq!(mh.0, Scalar::one());
-        
+        assert!(mh.is_empty());
+
         mh = mh.add(2.into(), 1);
         mh = mh.remove(2.into(), 1);
-        assert_eq!(mh.0, Scalar::one());
+        assert!(mh.is_empty());
 
         mh = mh.add(5.into(), 4);
         for _ in 0..4 {
             mh = mh.remove(5.into(), 1);
         }
-        assert_eq!(mh.0, Scalar::one());
+        assert!(mh.is_empty());
 
         for _ in 0..27 {
             mh = mh.add(3.into(), 1);
         }
         mh = mh.remove(3.into(), 27);
-        assert_eq!(mh.0, Scalar::one());
+        assert!(mh.is_empty());
     }
 
     #[test]
@@ -105,7 +287,7 @@ mod tests {
             right = right.add(elem, count);
         }
 
-        let u = left.multiset_union(&right);
+        let u = &left * &right;
         let mut check = MultisetHash::new();
         for &(elem, count) in a.iter() {
             check = check.add(elem, count);
@@ -113,7 +295,7 @@ mod tests {
         for &(elem, count) in b.iter() {
             check = check.add(elem, count);
         }
-        assert_eq!(u.0, check.0);
+        assert_eq!(u, check);
     }
 
     #[test]
@@ -130,13 +312,133 @@ mod tests {
             right = right.add(elem, count);
         }
 
-        let intersection = left.multiset_difference(&right);
+        let intersection = &left / &right;
         let mut check = MultisetHash::new();
         check = check.add(50.into(), 1);
         check = check.add(10.into(), 4);
         check = check.remove(7.into(), 4);
         check = check.remove(2.into(), 4);
         check = check.remove(6.into(), 1);
-        assert_eq!(intersection.0, check.0);
+        assert_eq!(intersection, check);
+    }
+
+    #[test]
+    fn test_from_iterator_and_extend() {
+        let pairs: Vec<(Scalar, u64)> = vec![(2.into(), 1), (10.into(), 4), (4.into(), 1)];
+        let collected: MultisetHash<Scalar> = pairs.iter().copied().collect();
+
+        let mut built = MultisetHash::new();
+        for &(elem, count) in pairs.iter() {
+            built = built.add(elem, count);
+        }
+        assert_eq!(collected, built);
+
+        let mut extended = MultisetHash::new();
+        extended.extend(pairs.iter().copied());
+        assert_eq!(extended, built);
+
+        let elems: Vec<Scalar> = vec![2.into(), 2.into(), 3.into()];
+        let from_elems: MultisetHash<Scalar> = elems.iter().copied().collect();
+        let mut check = MultisetHash::new();
+        check = check.add(2.into(), 1);
+        check = check.add(2.into(), 1);
+        check = check.add(3.into(), 1);
+        assert_eq!(from_elems, check);
+    }
+
+    #[test]
+    fn test_ct_eq() {
+        let mut a = MultisetHash::<Scalar>::new();
+        a = a.add(2.into(), 1);
+        a = a.add(10.into(), 4);
+
+        let mut b = MultisetHash::<Scalar>::new();
+        b = b.add(10.into(), 4);
+        b = b.add(2.into(), 1);
+
+        assert!(bool::from(a.ct_eq(&b)));
+        assert_eq!(a, b);
+
+        let c = MultisetHash::<Scalar>::new();
+        assert!(!bool::from(a.ct_eq(&c)));
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let entries: Vec<(Scalar, u64)> = vec![
+            (2.into(), 1),
+            (10.into(), 4),
+            (4.into(), 1),
+            (7.into(), 3),
+            (3.into(), 7),
+            (11.into(), 0),
+            (9.into(), u64::MAX),
+        ];
+
+        let batched = MultisetHash::from_slice(&entries);
+
+        let mut folded = MultisetHash::new();
+        for &(elem, count) in entries.iter() {
+            folded = folded.add(elem, count);
+        }
+
+        assert_eq!(batched, folded);
+    }
+
+    #[test]
+    #[should_panic(expected = "elements must be nonzero")]
+    fn test_from_slice_rejects_zero_elements() {
+        let entries: Vec<(Scalar, u64)> = vec![(Scalar::zero(), 1)];
+        let _ = MultisetHash::from_slice(&entries);
+    }
+
+    struct TestElem(u64);
+
+    impl HashToField<Scalar> for TestElem {
+        fn hash_to_field(&self) -> Scalar {
+            self.0.into()
+        }
+    }
+
+    #[test]
+    fn test_from_slice_elems() {
+        let entries = vec![(TestElem(2), 1), (TestElem(10), 4), (TestElem(4), 1)];
+        let batched = MultisetHash::<Scalar>::from_slice_elems(&entries);
+
+        let mut folded = MultisetHash::new();
+        folded = folded.add_elem(TestElem(2), 1);
+        folded = folded.add_elem(TestElem(10), 4);
+        folded = folded.add_elem(TestElem(4), 1);
+
+        assert_eq!(batched, folded);
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let mut mh = MultisetHash::<Scalar>::new();
+        mh = mh.add(2.into(), 1);
+        mh = mh.add(10.into(), 4);
+
+        let bytes = mh.to_bytes();
+        let decoded = MultisetHash::<Scalar>::from_bytes(&bytes).expect("valid encoding");
+        assert_eq!(mh, decoded);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_zero() {
+        let zero = MultisetHash(Scalar::zero());
+        assert!(MultisetHash::<Scalar>::from_bytes(&zero.to_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_wrong_length() {
+        assert!(MultisetHash::<Scalar>::from_bytes(&[0u8; 4]).is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_non_canonical() {
+        let bytes = [0xffu8; 32];
+        assert!(MultisetHash::<Scalar>::from_bytes(&bytes).is_none());
     }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+use crate::MultisetHash;
+use ff::Field;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Aggregates [`MultisetHash`] reports from a set of replicas and decides whether enough of them
+/// agree on the same multiset to call it committed.
+pub struct Quorum<Id, F> {
+    voters: HashSet<Id>,
+    threshold: usize,
+    reports: HashMap<Id, MultisetHash<F>>,
+}
+
+impl<Id: Eq + Hash + Clone, F: Field> Quorum<Id, F> {
+    /// constructs a quorum over the given voter set, requiring a strict majority (more than half
+    /// of `voters`) to agree before a hash is considered committed.
+    pub fn new(voters: impl IntoIterator<Item = Id>) -> Self {
+        let voters: HashSet<Id> = voters.into_iter().collect();
+        let threshold = voters.len() / 2 + 1;
+        Quorum {
+            voters,
+            threshold,
+            reports: HashMap::new(),
+        }
+    }
+
+    /// same as [`Quorum::new`], but lets the caller pick an arbitrary `threshold` instead of the
+    /// default strict-majority rule. `threshold` must be greater than `voters.len() / 2`, or two
+    /// disjoint sets of replicas could both reach quorum on different hashes at once, in which
+    /// case [`Quorum::committed`] picks between them in an unspecified order.
+    pub fn with_threshold(voters: impl IntoIterator<Item = Id>, threshold: usize) -> Self {
+        Quorum {
+            voters: voters.into_iter().collect(),
+            threshold,
+            reports: HashMap::new(),
+        }
+    }
+
+    /// records (or overwrites) the hash reported by `replica_id`. Reports from replicas outside
+    /// the voter set are ignored.
+    pub fn report(&mut self, replica_id: Id, hash: MultisetHash<F>) {
+        if self.voters.contains(&replica_id) {
+            self.reports.insert(replica_id, hash);
+        }
+    }
+
+    /// returns the hash that a quorum (at least `threshold` voters) agree on, if one exists.
+    pub fn committed(&self) -> Option<MultisetHash<F>> {
+        let mut buckets: Vec<(&MultisetHash<F>, usize)> = Vec::new();
+        for hash in self.reports.values() {
+            if let Some(bucket) = buckets.iter_mut().find(|(h, _)| *h == hash) {
+                bucket.1 += 1;
+            } else {
+                buckets.push((hash, 1));
+            }
+        }
+
+        buckets
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .filter(|(_, count)| *count >= self.threshold)
+            .map(|(hash, _)| MultisetHash(hash.0))
+    }
+
+    /// returns the replicas whose reported hash does not match the [`Quorum::committed`] hash.
+    /// If no hash has reached quorum, every reporting replica is considered divergent, since no
+    /// majority has formed yet.
+    pub fn divergent(&self) -> Vec<Id> {
+        match self.committed() {
+            Some(committed) => self
+                .reports
+                .iter()
+                .filter(|(_, hash)| **hash != committed)
+                .map(|(id, _)| id.clone())
+                .collect(),
+            None => self.reports.keys().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::Scalar;
+
+    fn hash(elems: &[(Scalar, u64)]) -> MultisetHash<Scalar> {
+        let mut h = MultisetHash::new();
+        for &(elem, count) in elems {
+            h = h.add(elem, count);
+        }
+        h
+    }
+
+    #[test]
+    fn test_committed_with_majority() {
+        let mut quorum: Quorum<&str, Scalar> = Quorum::new(["a", "b", "c", "d"]);
+        let agreed = hash(&[(2.into(), 3), (5.into(), 1)]);
+        let other = hash(&[(7.into(), 1)]);
+
+        quorum.report("a", agreed);
+        quorum.report("b", agreed);
+        quorum.report("c", agreed);
+        quorum.report("d", other);
+
+        assert_eq!(quorum.committed(), Some(agreed));
+        assert_eq!(quorum.divergent(), vec!["d"]);
+    }
+
+    #[test]
+    fn test_no_committed_without_majority() {
+        let mut quorum: Quorum<&str, Scalar> = Quorum::new(["a", "b", "c", "d"]);
+        quorum.report("a", hash(&[(2.into(), 1)]));
+        quorum.report("b", hash(&[(3.into(), 1)]));
+        quorum.report("c", hash(&[(4.into(), 1)]));
+        quorum.report("d", hash(&[(5.into(), 1)]));
+
+        assert_eq!(quorum.committed(), None);
+        let mut divergent = quorum.divergent();
+        divergent.sort();
+        assert_eq!(divergent, vec!["a", "b", "c", "d"]);
+    }
+}